@@ -3,17 +3,32 @@
 use proc_macro::TokenStream;
 
 use quote::quote;
-use syn::{Attribute, Data, DeriveInput, Fields, LitStr, parse_macro_input};
+use syn::{
+    parse_macro_input, punctuated::Punctuated, token::Comma, Attribute, Data, DataEnum, Field,
+    Fields, LitStr, Variant,
+};
 
 /// A derive macro to implement [`serde::Serialize`] with arbitrary extra fields specified via
-/// `#[more(key="...", value="...")]` attributes. The `value` should be a method on the struct that
+/// `#[more(key="...", value="...")]` attributes. The `value` should be a method on the type that
 /// returns a type implementing `serde::Serialize`.
 ///
-/// Works with `serde` and `serde_with` attributes on the struct and its fields.
+/// Works with `serde` and `serde_with` attributes on the container and its fields.
 ///
-/// ## Limitations
+/// Supports named-field structs, tuple structs, unit structs, and enums (with `#[more(...)]`
+/// attached to the enum itself, to individual variants, or both).
 ///
-/// - Currently only supports structs with named fields.
+/// Add `skip_serializing_if="path::to::predicate"` to suppress an extra field at runtime, the
+/// same way `#[serde(skip_serializing_if = "...")]` does for a real field. The predicate is
+/// called as `predicate(&value)` and must return `bool`.
+///
+/// Add `as="display"` to serialize a computed field through its [`std::fmt::Display`]
+/// implementation instead of `Serialize`, for values that have no natural serde form (a
+/// formatted duration, a typed ID, ...).
+///
+/// Use `#[more(flatten="method")]` instead of `key`/`value` to splice a computed struct's own
+/// fields into the surrounding object, the same way `#[serde(flatten)]` does for a real field.
+/// The method's value must serialize as a struct; a map-shaped value (e.g. a `HashMap`) makes
+/// serialization fail.
 ///
 /// ## Example
 ///
@@ -68,19 +83,589 @@ use syn::{Attribute, Data, DeriveInput, Fields, LitStr, parse_macro_input};
 ///
 #[proc_macro_derive(SerializeMore, attributes(more, serde))]
 pub fn serialize_more_derive(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
+    let input = parse_macro_input!(input as syn::DeriveInput);
     let name = &input.ident;
 
-    let mut front_pairs: Vec<(String, String)> = Vec::new();
-    let mut back_pairs: Vec<(String, String)> = Vec::new();
-    for attr in &input.attrs {
-        match parse_more_attribute(attr) {
-            Ok(Some((k, v, true))) => front_pairs.push((k, v)),
-            Ok(Some((k, v, false))) => back_pairs.push((k, v)),
-            Ok(None) => {}
+    let container = match collect_more(&input.attrs) {
+        Ok(attrs) => attrs,
+        Err(e) => return TokenStream::from(e.to_compile_error()),
+    };
+
+    if container.capture.is_some() || container.rest.is_some() {
+        return syn::Error::new_spanned(
+            &input,
+            "`more(capture = ...)` and `more(rest = ...)` only apply to `#[derive(DeserializeMore)]`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let passthrough_attrs: Vec<_> = input
+        .attrs
+        .iter()
+        .filter(|attr| !attr.path().is_ident("more"))
+        .collect();
+
+    let body = match &input.data {
+        Data::Struct(data) => container_value(
+            &quote! { self },
+            &data.fields,
+            &quote! { stringify!(#name) },
+            &container,
+            &passthrough_attrs,
+        ),
+        Data::Enum(data) => match enum_body(data, &container, &passthrough_attrs) {
+            Ok(body) => body,
             Err(e) => return TokenStream::from(e.to_compile_error()),
+        },
+        Data::Union(_) => {
+            return syn::Error::new_spanned(&input, "`SerializeMore` does not support unions.")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let expanded = quote! {
+        #[automatically_derived]
+        impl serde::Serialize for #name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                #body
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// A single extra field declared via `#[more(...)]`: the map key, the method that produces its
+/// value, and an optional `skip_serializing_if` predicate (`&T -> bool`, mirroring serde's own
+/// attribute of the same name) that suppresses the entry at runtime.
+#[derive(Clone)]
+struct MorePair {
+    key: String,
+    value: String,
+    skip_if: Option<syn::Path>,
+    as_display: bool,
+}
+
+/// A single `#[more(...)]` attribute: either a keyed extra field, or a `flatten="method"` whose
+/// result gets spliced into the surrounding map.
+#[derive(Clone)]
+enum MoreEntry {
+    Pair(MorePair),
+    Flatten(String),
+}
+
+/// The `#[more(...)]` attributes collected from a container (struct or enum) or a single variant.
+#[derive(Default, Clone)]
+struct MoreAttrs {
+    front: Vec<MoreEntry>,
+    back: Vec<MoreEntry>,
+    data_key: Option<String>,
+    /// `#[more(capture="method")]`, for `DeserializeMore`: a method called with the leftover
+    /// fields once the known ones have been pulled out.
+    capture: Option<String>,
+    /// `#[more(rest="field")]`, for `DeserializeMore`: a field that the leftover fields are
+    /// assigned to directly instead of going through a method.
+    rest: Option<String>,
+}
+
+/// Builds the body of `Serialize::serialize` for a single struct-shaped value: the `fields` of
+/// `self` (or of an already-destructured enum variant, if `self_expr` is not `self`), plus the
+/// extra pairs declared via `#[more(...)]`.
+fn container_value(
+    self_expr: &proc_macro2::TokenStream,
+    fields: &Fields,
+    unit_name: &proc_macro2::TokenStream,
+    attrs: &MoreAttrs,
+    passthrough_attrs: &[&Attribute],
+) -> proc_macro2::TokenStream {
+    match fields {
+        Fields::Named(fields) => named_body(self_expr, &fields.named, attrs, passthrough_attrs),
+        Fields::Unnamed(fields) => {
+            unnamed_body(self_expr, &fields.unnamed, attrs, passthrough_attrs)
+        }
+        Fields::Unit => unit_body(unit_name, attrs),
+    }
+}
+
+/// Generates one map-mutating statement per entry: a guarded `map.serialize_entry(...)` for a
+/// pair, or a `serde_more::flatten_into(...)` call for a `flatten`.
+fn entry_statements(entries: &[MoreEntry]) -> Vec<proc_macro2::TokenStream> {
+    entries.iter().map(entry_statement).collect()
+}
+
+fn entry_statement(entry: &MoreEntry) -> proc_macro2::TokenStream {
+    match entry {
+        MoreEntry::Pair(pair) => pair_statement(pair),
+        MoreEntry::Flatten(method) => {
+            let call = method_call(method);
+            quote! {
+                serde_more::flatten_into(&mut map, &#call)?;
+            }
+        }
+    }
+}
+
+fn pair_statement(pair: &MorePair) -> proc_macro2::TokenStream {
+    let key = &pair.key;
+    let call = method_call(&pair.value);
+    match &pair.skip_if {
+        Some(predicate) => {
+            let value = as_display_expr(&quote! { v }, pair.as_display);
+            quote! {
+                {
+                    let v = #call;
+                    if !#predicate(&v) {
+                        map.serialize_entry(#key, #value)?;
+                    }
+                }
+            }
+        }
+        None => {
+            let value = as_display_expr(&call, pair.as_display);
+            quote! {
+                map.serialize_entry(#key, #value)?;
+            }
+        }
+    }
+}
+
+/// Wraps a value expression in [`serde_more::DisplayAsStr`] for `#[more(..., as="display")]`,
+/// otherwise serializes it by reference as-is.
+fn as_display_expr(value: &proc_macro2::TokenStream, as_display: bool) -> proc_macro2::TokenStream {
+    if as_display {
+        quote! { &serde_more::DisplayAsStr(&#value) }
+    } else {
+        quote! { &#value }
+    }
+}
+
+fn method_call(method: &str) -> proc_macro2::TokenStream {
+    let method_ident = syn::Ident::new(method, proc_macro2::Span::call_site());
+    quote! { self.#method_ident() }
+}
+
+/// Named-field ("struct style") containers keep the original `serialize_map` +
+/// `FlatMapSerializer` path: the real fields are flattened into the map and the extra pairs are
+/// spliced in around them.
+fn named_body(
+    self_expr: &proc_macro2::TokenStream,
+    fields: &Punctuated<Field, Comma>,
+    attrs: &MoreAttrs,
+    passthrough_attrs: &[&Attribute],
+) -> proc_macro2::TokenStream {
+    let field_names: Vec<_> = fields.iter().map(|f| &f.ident).collect();
+    let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    let field_attrs: Vec<_> = fields.iter().map(|f| &f.attrs).collect();
+
+    let helper_fields = field_names
+        .iter()
+        .zip(field_types.iter())
+        .zip(field_attrs.iter())
+        .map(|((name, ty), attrs)| {
+            quote! {
+                #(#attrs)*
+                #name: &'a #ty
+            }
+        });
+
+    let field_assignments = field_names
+        .iter()
+        .map(|name| quote! { #name: &#self_expr.#name });
+
+    let front_stmts = entry_statements(&attrs.front);
+    let back_stmts = entry_statements(&attrs.back);
+
+    quote! {
+        {
+            use serde::ser::SerializeMap;
+
+            #[derive(serde::Serialize)]
+            #(#passthrough_attrs)*
+            struct Helper<'a> {
+                #(#helper_fields,)*
+            }
+
+            let helper = Helper {
+                #(#field_assignments,)*
+            };
+
+            let mut map = serializer.serialize_map(None)?;
+
+            #(#front_stmts)*
+
+            {
+                let flat = serde_more::FlatMapSerializer { map: &mut map };
+                serde::Serialize::serialize(&helper, flat)?;
+            }
+
+            #(#back_stmts)*
+
+            map.end()
         }
     }
+}
+
+/// Tuple structs and newtypes have no natural map representation, so they only grow a map when
+/// there are extra fields to attach; otherwise they serialize exactly like the underlying tuple.
+fn unnamed_body(
+    self_expr: &proc_macro2::TokenStream,
+    fields: &Punctuated<Field, Comma>,
+    attrs: &MoreAttrs,
+    passthrough_attrs: &[&Attribute],
+) -> proc_macro2::TokenStream {
+    let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    let field_attrs: Vec<_> = fields.iter().map(|f| &f.attrs).collect();
+    let indices: Vec<_> = (0..fields.len()).map(syn::Index::from).collect();
+
+    let helper_fields = field_types
+        .iter()
+        .zip(field_attrs.iter())
+        .map(|(ty, attrs)| quote! { #(#attrs)* &'a #ty });
+
+    let helper_assignments = indices.iter().map(|i| quote! { &#self_expr.#i });
+
+    let helper = quote! {
+        #[derive(serde::Serialize)]
+        #(#passthrough_attrs)*
+        struct Helper<'a>( #(#helper_fields,)* );
+
+        let helper = Helper( #(#helper_assignments,)* );
+    };
+
+    if attrs.front.is_empty() && attrs.back.is_empty() {
+        quote! {
+            {
+                #helper
+                serde::Serialize::serialize(&helper, serializer)
+            }
+        }
+    } else {
+        let data_key = attrs.data_key.as_deref().unwrap_or("data");
+        let front_stmts = entry_statements(&attrs.front);
+        let back_stmts = entry_statements(&attrs.back);
+
+        quote! {
+            {
+                use serde::ser::SerializeMap;
+
+                #helper
+
+                let mut map = serializer.serialize_map(None)?;
+
+                #(#front_stmts)*
+                map.serialize_entry(#data_key, &helper)?;
+                #(#back_stmts)*
+
+                map.end()
+            }
+        }
+    }
+}
+
+/// Unit structs (and unit variants) carry no data at all, so with no extra fields they serialize
+/// as a plain unit; with extra fields the map consists entirely of those pairs.
+fn unit_body(unit_name: &proc_macro2::TokenStream, attrs: &MoreAttrs) -> proc_macro2::TokenStream {
+    if attrs.front.is_empty() && attrs.back.is_empty() {
+        quote! { serializer.serialize_unit_struct(#unit_name) }
+    } else {
+        let front_stmts = entry_statements(&attrs.front);
+        let back_stmts = entry_statements(&attrs.back);
+        quote! {
+            {
+                use serde::ser::SerializeMap;
+
+                let mut map = serializer.serialize_map(None)?;
+
+                #(#front_stmts)*
+                #(#back_stmts)*
+
+                map.end()
+            }
+        }
+    }
+}
+
+/// Builds the `match self { ... }` used for enums: `#[more(...)]` attached to the enum applies
+/// to every arm, attributes on a variant apply only there, and the two are combined (enum-level
+/// pairs on the outside, variant-level pairs closest to the variant's own data). Non-`more`
+/// attributes (e.g. `#[serde(...)]`) on the enum and on the variant are passed through onto the
+/// variant's `Helper` the same way, container-level first.
+fn enum_body(
+    data: &DataEnum,
+    container: &MoreAttrs,
+    container_passthrough_attrs: &[&Attribute],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut arms = Vec::new();
+
+    for variant in &data.variants {
+        arms.push(enum_arm(variant, container, container_passthrough_attrs)?);
+    }
+
+    Ok(quote! {
+        match self {
+            #(#arms)*
+        }
+    })
+}
+
+fn enum_arm(
+    variant: &Variant,
+    container: &MoreAttrs,
+    container_passthrough_attrs: &[&Attribute],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let variant_attrs = collect_more(&variant.attrs)?;
+    let merged = MoreAttrs {
+        front: container
+            .front
+            .iter()
+            .cloned()
+            .chain(variant_attrs.front)
+            .collect(),
+        back: variant_attrs
+            .back
+            .into_iter()
+            .chain(container.back.iter().cloned())
+            .collect(),
+        data_key: variant_attrs
+            .data_key
+            .or_else(|| container.data_key.clone()),
+        capture: variant_attrs.capture.or_else(|| container.capture.clone()),
+        rest: variant_attrs.rest.or_else(|| container.rest.clone()),
+    };
+
+    let variant_passthrough_attrs = variant
+        .attrs
+        .iter()
+        .filter(|attr| !attr.path().is_ident("more"));
+    let passthrough_attrs: Vec<_> = container_passthrough_attrs
+        .iter()
+        .copied()
+        .chain(variant_passthrough_attrs)
+        .collect();
+
+    let variant_ident = &variant.ident;
+    let unit_name = quote! { stringify!(#variant_ident) };
+
+    let pattern = match &variant.fields {
+        Fields::Named(fields) => {
+            let names: Vec<_> = fields
+                .named
+                .iter()
+                .map(|f| f.ident.as_ref().unwrap())
+                .collect();
+            quote! { Self::#variant_ident { #(#names),* } }
+        }
+        Fields::Unnamed(fields) => {
+            let bindings: Vec<_> = (0..fields.unnamed.len())
+                .map(|i| syn::Ident::new(&format!("v{i}"), proc_macro2::Span::call_site()))
+                .collect();
+            quote! { Self::#variant_ident( #(#bindings),* ) }
+        }
+        Fields::Unit => quote! { Self::#variant_ident },
+    };
+
+    let body = match &variant.fields {
+        Fields::Named(fields) => {
+            let bound_names: Vec<_> = fields
+                .named
+                .iter()
+                .map(|f| f.ident.as_ref().unwrap())
+                .collect();
+            named_body_from_bindings(&bound_names, &fields.named, &merged, &passthrough_attrs)
+        }
+        Fields::Unnamed(fields) => {
+            let bindings: Vec<_> = (0..fields.unnamed.len())
+                .map(|i| syn::Ident::new(&format!("v{i}"), proc_macro2::Span::call_site()))
+                .collect();
+            unnamed_body_from_bindings(&bindings, &fields.unnamed, &merged, &passthrough_attrs)
+        }
+        Fields::Unit => unit_body(&unit_name, &merged),
+    };
+
+    Ok(quote! { #pattern => #body, })
+}
+
+/// Like [`named_body`], but the fields are already bound by a `match` pattern (to `&'a Ty`, by
+/// match ergonomics) instead of living behind `self.field`.
+fn named_body_from_bindings(
+    bound_names: &[&syn::Ident],
+    fields: &Punctuated<Field, Comma>,
+    attrs: &MoreAttrs,
+    passthrough_attrs: &[&Attribute],
+) -> proc_macro2::TokenStream {
+    let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    let field_attrs: Vec<_> = fields.iter().map(|f| &f.attrs).collect();
+
+    let helper_fields = bound_names
+        .iter()
+        .zip(field_types.iter())
+        .zip(field_attrs.iter())
+        .map(|((name, ty), attrs)| {
+            quote! {
+                #(#attrs)*
+                #name: &'a #ty
+            }
+        });
+
+    let front_stmts = entry_statements(&attrs.front);
+    let back_stmts = entry_statements(&attrs.back);
+
+    quote! {
+        {
+            use serde::ser::SerializeMap;
+
+            #[derive(serde::Serialize)]
+            #(#passthrough_attrs)*
+            struct Helper<'a> {
+                #(#helper_fields,)*
+            }
+
+            let helper = Helper {
+                #(#bound_names,)*
+            };
+
+            let mut map = serializer.serialize_map(None)?;
+
+            #(#front_stmts)*
+
+            {
+                let flat = serde_more::FlatMapSerializer { map: &mut map };
+                serde::Serialize::serialize(&helper, flat)?;
+            }
+
+            #(#back_stmts)*
+
+            map.end()
+        }
+    }
+}
+
+/// Like [`unnamed_body`], but the fields are already bound by a `match` pattern.
+fn unnamed_body_from_bindings(
+    bindings: &[syn::Ident],
+    fields: &Punctuated<Field, Comma>,
+    attrs: &MoreAttrs,
+    passthrough_attrs: &[&Attribute],
+) -> proc_macro2::TokenStream {
+    let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    let field_attrs: Vec<_> = fields.iter().map(|f| &f.attrs).collect();
+
+    let helper_fields = field_types
+        .iter()
+        .zip(field_attrs.iter())
+        .map(|(ty, attrs)| quote! { #(#attrs)* &'a #ty });
+
+    let helper = quote! {
+        #[derive(serde::Serialize)]
+        #(#passthrough_attrs)*
+        struct Helper<'a>( #(#helper_fields,)* );
+
+        let helper = Helper( #(#bindings,)* );
+    };
+
+    if attrs.front.is_empty() && attrs.back.is_empty() {
+        quote! {
+            {
+                #helper
+                serde::Serialize::serialize(&helper, serializer)
+            }
+        }
+    } else {
+        let data_key = attrs.data_key.as_deref().unwrap_or("data");
+        let front_stmts = entry_statements(&attrs.front);
+        let back_stmts = entry_statements(&attrs.back);
+
+        quote! {
+            {
+                use serde::ser::SerializeMap;
+
+                #helper
+
+                let mut map = serializer.serialize_map(None)?;
+
+                #(#front_stmts)*
+                map.serialize_entry(#data_key, &helper)?;
+                #(#back_stmts)*
+
+                map.end()
+            }
+        }
+    }
+}
+
+/// A derive macro to implement [`serde::Deserialize`] for named-field structs that tolerates
+/// unannounced incoming keys instead of erroring on them, optionally routing them somewhere
+/// useful via `#[more(capture="method")]` or `#[more(rest="field")]`.
+///
+/// Works by deserializing through a private `Helper` struct that mirrors the container's real
+/// fields (with their own `#[serde(...)]` attributes carried over unchanged), plus — when
+/// `capture` or `rest` is used — a hidden `#[serde(flatten)]` field that serde's own flatten
+/// support buffers every other key into. This is the read-side mirror of what
+/// `#[derive(SerializeMore)]`'s `flatten` mode does on the way out.
+///
+/// `#[more(rest="field")]` assigns the leftover entries directly to `field`, which must be a
+/// `HashMap<String, serde_json::Value>` (or another map-like type `#[serde(flatten)]` accepts).
+///
+/// `#[more(capture="method")]` instead calls `method(&mut self, extra: HashMap<String,
+/// serde_json::Value>)` once the struct's known fields have been assembled, letting the type
+/// decide what to do with the rest itself rather than just storing it.
+///
+/// `capture` and `rest` are mutually exclusive, and only one may be declared on a container.
+///
+/// ## Example
+///
+/// ```rust
+/// use serde_more::DeserializeMore;
+/// use serde_json::json;
+/// use std::collections::HashMap;
+///
+/// #[derive(DeserializeMore, Debug, PartialEq)]
+/// #[more(rest = "extra")]
+/// struct Event {
+///     name: String,
+///     extra: HashMap<String, serde_json::Value>,
+/// }
+///
+/// fn main() {
+///     let event: Event = serde_json::from_value(json!({
+///         "name": "deploy",
+///         "region": "eu-west-1",
+///     }))
+///     .unwrap();
+///     assert_eq!(event.name, "deploy");
+///     assert_eq!(event.extra["region"], "eu-west-1");
+/// }
+/// ```
+#[proc_macro_derive(DeserializeMore, attributes(more, serde))]
+pub fn deserialize_more_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    let name = &input.ident;
+
+    let container = match collect_more(&input.attrs) {
+        Ok(attrs) => attrs,
+        Err(e) => return TokenStream::from(e.to_compile_error()),
+    };
+
+    if !container.front.is_empty() || !container.back.is_empty() || container.data_key.is_some() {
+        return syn::Error::new_spanned(
+            &input,
+            "`more(key = ...)`, `more(value = ...)`, `more(flatten = ...)`, and \
+             `more(data_key = ...)` only apply to `#[derive(SerializeMore)]`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let passthrough_attrs: Vec<_> = input
+        .attrs
+        .iter()
+        .filter(|attr| !attr.path().is_ident("more"))
+        .collect();
 
     let fields = match &input.data {
         Data::Struct(data) => match &data.fields {
@@ -88,29 +673,91 @@ pub fn serialize_more_derive(input: TokenStream) -> TokenStream {
             _ => {
                 return syn::Error::new_spanned(
                     &input,
-                    "`SerializeMore` only supports structs with named fields.",
+                    "`DeserializeMore` only supports named-field structs.",
                 )
                 .to_compile_error()
                 .into();
             }
         },
         _ => {
-            return syn::Error::new_spanned(&input, "`SerializeMore` only supports structs.")
-                .to_compile_error()
-                .into();
+            return syn::Error::new_spanned(
+                &input,
+                "`DeserializeMore` only supports named-field structs.",
+            )
+            .to_compile_error()
+            .into();
         }
     };
 
-    let field_names: Vec<_> = fields.iter().map(|f| &f.ident).collect();
-    let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
-    let field_attrs: Vec<_> = fields.iter().map(|f| &f.attrs).collect();
+    let body = match deserialize_more_body(name, fields, &container, &passthrough_attrs) {
+        Ok(body) => body,
+        Err(e) => return TokenStream::from(e.to_compile_error()),
+    };
 
-    let struct_attrs: Vec<_> = input
-        .attrs
+    TokenStream::from(body)
+}
+
+/// Checks whether any of a container's passed-through attributes is a
+/// `#[serde(deny_unknown_fields)]`, which conflicts with the hidden `#[serde(flatten)]` field
+/// `capture`/`rest` generates.
+fn has_deny_unknown_fields(attrs: &[&Attribute]) -> syn::Result<bool> {
+    for attr in attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+
+        let metas = attr.parse_args_with(Punctuated::<syn::Meta, Comma>::parse_terminated)?;
+        if metas
+            .iter()
+            .any(|meta| meta.path().is_ident("deny_unknown_fields"))
+        {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Builds `impl Deserialize for #name`, deserializing through a private `Helper` struct that
+/// shares the container's real fields (minus the `rest` field itself, if any) and, when `capture`
+/// or `rest` is set, a hidden `#[serde(flatten)]` field that serde buffers every unrecognized key
+/// into.
+fn deserialize_more_body(
+    name: &syn::Ident,
+    fields: &Punctuated<Field, Comma>,
+    attrs: &MoreAttrs,
+    passthrough_attrs: &[&Attribute],
+) -> syn::Result<proc_macro2::TokenStream> {
+    if attrs.capture.is_some() && attrs.rest.is_some() {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`more(capture = ...)` and `more(rest = ...)` are mutually exclusive",
+        ));
+    }
+
+    let wants_rest_field = attrs.capture.is_some() || attrs.rest.is_some();
+    if wants_rest_field && has_deny_unknown_fields(passthrough_attrs)? {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`#[serde(deny_unknown_fields)]` can't be combined with `more(capture = ...)` or \
+             `more(rest = ...)`, since both are implemented via `#[serde(flatten)]`, which serde \
+             itself forbids alongside `deny_unknown_fields`",
+        ));
+    }
+
+    let rest_field_name = attrs.rest.as_deref();
+    let regular_fields: Vec<&Field> = fields
         .iter()
-        .filter(|attr| !attr.path().is_ident("more"))
+        .filter(|f| Some(f.ident.as_ref().unwrap().to_string().as_str()) != rest_field_name)
         .collect();
 
+    let field_names: Vec<_> = regular_fields
+        .iter()
+        .map(|f| f.ident.as_ref().unwrap())
+        .collect();
+    let field_types: Vec<_> = regular_fields.iter().map(|f| &f.ty).collect();
+    let field_attrs: Vec<_> = regular_fields.iter().map(|f| &f.attrs).collect();
+
     let helper_fields = field_names
         .iter()
         .zip(field_types.iter())
@@ -118,116 +765,204 @@ pub fn serialize_more_derive(input: TokenStream) -> TokenStream {
         .map(|((name, ty), attrs)| {
             quote! {
                 #(#attrs)*
-                #name: &'a #ty
+                #name: #ty
             }
         });
 
-    let field_assignments = field_names.iter().map(|name| {
-        quote! { #name: &self.#name }
-    });
-
-    let front_keys: Vec<_> = front_pairs.iter().map(|(k, _)| k).collect();
-    let front_methods: Vec<_> = front_pairs
-        .iter()
-        .map(|(_, v)| {
-            let method_ident = syn::Ident::new(v, proc_macro2::Span::call_site());
-            quote! { self.#method_ident() }
+    let rest_decl = if let Some(field) = &attrs.rest {
+        let rest_ty = fields
+            .iter()
+            .find(|f| f.ident.as_ref().unwrap().to_string().as_str() == field.as_str())
+            .map(|f| &f.ty)
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    name,
+                    format!("`more(rest = \"{field}\")` names a field that doesn't exist"),
+                )
+            })?;
+        Some(quote! {
+            #[serde(flatten)]
+            __more_rest: #rest_ty,
         })
-        .collect();
-
-    let back_keys: Vec<_> = back_pairs.iter().map(|(k, _)| k).collect();
-    let back_methods: Vec<_> = back_pairs
-        .iter()
-        .map(|(_, v)| {
-            let method_ident = syn::Ident::new(v, proc_macro2::Span::call_site());
-            quote! { self.#method_ident() }
+    } else if attrs.capture.is_some() {
+        Some(quote! {
+            #[serde(flatten)]
+            __more_rest: std::collections::HashMap<String, serde_json::Value>,
         })
-        .collect();
+    } else {
+        None
+    };
 
-    let serialize_impl = quote! {
-        impl serde::Serialize for #name {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    let construct = if let Some(method) = &attrs.capture {
+        let method_ident = syn::Ident::new(method, proc_macro2::Span::call_site());
+        quote! {
+            let mut value = #name {
+                #(#field_names: helper.#field_names,)*
+            };
+            value.#method_ident(helper.__more_rest);
+            Ok(value)
+        }
+    } else if let Some(field) = &attrs.rest {
+        let rest_ident = syn::Ident::new(field, proc_macro2::Span::call_site());
+        quote! {
+            Ok(#name {
+                #(#field_names: helper.#field_names,)*
+                #rest_ident: helper.__more_rest,
+            })
+        }
+    } else {
+        quote! {
+            Ok(#name {
+                #(#field_names: helper.#field_names,)*
+            })
+        }
+    };
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl<'de> serde::Deserialize<'de> for #name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
             where
-                S: serde::Serializer,
+                D: serde::Deserializer<'de>,
             {
-                use serde::ser::{Serializer, SerializeMap};
-
-                #[derive(serde::Serialize)]
-                #(#struct_attrs)*
-                struct Helper<'a> {
+                #[derive(serde::Deserialize)]
+                #(#passthrough_attrs)*
+                struct Helper {
                     #(#helper_fields,)*
+                    #rest_decl
                 }
 
-                let helper = Helper {
-                    #(#field_assignments,)*
-                };
+                let helper = <Helper as serde::Deserialize>::deserialize(deserializer)?;
+                #construct
+            }
+        }
+    })
+}
 
-                let mut map = serializer.serialize_map(None)?;
+fn collect_more(attrs: &[Attribute]) -> syn::Result<MoreAttrs> {
+    let mut collected = MoreAttrs::default();
 
-                #(
-                    map.serialize_entry(#front_keys, &#front_methods)?;
-                )*
+    for attr in attrs {
+        if !attr.path().is_ident("more") {
+            continue;
+        }
 
-                {
-                    let flat = serde_more::FlatMapSerializer {
-                        map: &mut map,
-                    };
-                    helper.serialize(flat)?;
+        let mut key: Option<String> = None;
+        let mut value: Option<String> = None;
+        let mut is_front = false;
+        let mut data_key: Option<String> = None;
+        let mut skip_if: Option<syn::Path> = None;
+        let mut as_display = false;
+        let mut flatten: Option<String> = None;
+        let mut capture: Option<String> = None;
+        let mut rest: Option<String> = None;
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("key") || meta.path.is_ident("k") {
+                let lit: LitStr = meta.value()?.parse()?;
+                key = Some(lit.value());
+                Ok(())
+            } else if meta.path.is_ident("value") || meta.path.is_ident("v") {
+                let lit: LitStr = meta.value()?.parse()?;
+                value = Some(lit.value());
+                Ok(())
+            } else if meta.path.is_ident("position") {
+                let lit: LitStr = meta.value()?.parse()?;
+                is_front = match lit.value().to_ascii_lowercase().as_str() {
+                    "front" => true,
+                    "back" => false,
+                    invalid => {
+                        return Err(syn::Error::new_spanned(
+                            attr,
+                            format!("invalid position '{invalid}', expected 'front' or 'back'"),
+                        ));
+                    }
+                };
+                Ok(())
+            } else if meta.path.is_ident("data_key") {
+                let lit: LitStr = meta.value()?.parse()?;
+                data_key = Some(lit.value());
+                Ok(())
+            } else if meta.path.is_ident("skip_serializing_if") {
+                let lit: LitStr = meta.value()?.parse()?;
+                skip_if = Some(lit.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("as") {
+                let lit: LitStr = meta.value()?.parse()?;
+                match lit.value().as_str() {
+                    "display" => as_display = true,
+                    invalid => {
+                        return Err(syn::Error::new_spanned(
+                            attr,
+                            format!("invalid as '{invalid}', expected 'display'"),
+                        ));
+                    }
                 }
+                Ok(())
+            } else if meta.path.is_ident("flatten") {
+                let lit: LitStr = meta.value()?.parse()?;
+                flatten = Some(lit.value());
+                Ok(())
+            } else if meta.path.is_ident("capture") {
+                let lit: LitStr = meta.value()?.parse()?;
+                capture = Some(lit.value());
+                Ok(())
+            } else if meta.path.is_ident("rest") {
+                let lit: LitStr = meta.value()?.parse()?;
+                rest = Some(lit.value());
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "unsupported attribute key, expected 'key', 'k', 'value', 'v', 'position', \
+                     'data_key', 'skip_serializing_if', 'as', 'flatten', 'capture', or 'rest'",
+                ))
+            }
+        })?;
 
-                #(
-                    map.serialize_entry(#back_keys, &#back_methods)?;
-                )*
+        // `data_key`, `capture`, and `rest` are independent, container-wide settings rather than
+        // entries in `front`/`back`, but they can still be declared in the same `#[more(...)]` as
+        // a `key`/`value`/`flatten` entry (e.g. `#[more(key = "sum", data_key = "payload")]`), so
+        // fall through to the entry handling below instead of `continue`-ing past it.
+        if let Some(dk) = data_key {
+            collected.data_key = Some(dk);
+        }
 
-                map.end()
-            }
+        if let Some(method) = capture {
+            collected.capture = Some(method);
         }
-    };
 
-    TokenStream::from(serialize_impl)
-}
-
-fn parse_more_attribute(attr: &Attribute) -> syn::Result<Option<(String, String, bool)>> {
-    if !attr.path().is_ident("more") {
-        return Ok(None);
-    }
-
-    let mut key: Option<String> = None;
-    let mut value: Option<String> = None;
-    let mut is_front: bool = false;
-
-    attr.parse_nested_meta(|meta| {
-        if meta.path.is_ident("key") || meta.path.is_ident("k") {
-            let lit: LitStr = meta.value()?.parse()?;
-            key = Some(lit.value());
-            Ok(())
-        } else if meta.path.is_ident("value") || meta.path.is_ident("v") {
-            let lit: LitStr = meta.value()?.parse()?;
-            value = Some(lit.value());
-            Ok(())
-        } else if meta.path.is_ident("position") {
-            let lit: LitStr = meta.value()?.parse()?;
-            is_front = match lit.value().to_ascii_lowercase().as_str() {
-                "front" => true,
-                "back" => false,
-                invalid => {
-                    return Err(syn::Error::new_spanned(
-                        attr,
-                        format!("invalid position '{invalid}', expected 'front' or 'back'"),
-                    ));
-                }
-            };
-            Ok(())
-        } else {
-            Err(meta.error(
-                "unsupported attribute key, expected 'key', 'k', 'value', 'v', or 'position'",
-            ))
+        if let Some(field) = rest {
+            collected.rest = Some(field);
         }
-    })?;
 
-    match (key, value) {
-        (Some(k), Some(v)) => Ok(Some((k, v, is_front))),
-        (Some(k), None) => Ok(Some((k.clone(), k, is_front))),
-        _ => Ok(None),
+        let entry = if let Some(method) = flatten {
+            Some(MoreEntry::Flatten(method))
+        } else {
+            match (key, value) {
+                (Some(k), Some(v)) => Some(MoreEntry::Pair(MorePair {
+                    key: k,
+                    value: v,
+                    skip_if,
+                    as_display,
+                })),
+                (Some(k), None) => Some(MoreEntry::Pair(MorePair {
+                    key: k.clone(),
+                    value: k,
+                    skip_if,
+                    as_display,
+                })),
+                _ => None,
+            }
+        };
+
+        if let Some(entry) = entry {
+            if is_front {
+                collected.front.push(entry);
+            } else {
+                collected.back.push(entry);
+            }
+        }
     }
+
+    Ok(collected)
 }
@@ -0,0 +1,100 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::json;
+use serde_more::DeserializeMore;
+use testresult::TestResult;
+
+#[derive(DeserializeMore, Debug, PartialEq)]
+#[more(rest = "extra")]
+struct Event {
+    name: String,
+    extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(DeserializeMore, Debug, PartialEq)]
+#[more(rest = "extra")]
+struct OrderedEvent {
+    name: String,
+    extra: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(DeserializeMore, Debug, PartialEq)]
+struct Plain {
+    id: u32,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Tally {
+    count: usize,
+}
+
+#[derive(DeserializeMore, Debug, PartialEq)]
+#[more(capture = "absorb")]
+struct Counting {
+    id: u32,
+    #[serde(skip)]
+    tally: Tally,
+}
+
+impl Counting {
+    fn absorb(&mut self, extra: HashMap<String, serde_json::Value>) {
+        self.tally.count = extra.len();
+    }
+}
+
+#[test]
+fn rest_captures_unknown_fields() -> TestResult {
+    let event: Event = serde_json::from_value(json!({
+        "name": "deploy",
+        "region": "eu-west-1",
+        "attempt": 2,
+    }))?;
+
+    assert_eq!(event.name, "deploy");
+    assert_eq!(event.extra["region"], json!("eu-west-1"));
+    assert_eq!(event.extra["attempt"], json!(2));
+    assert_eq!(event.extra.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn rest_stays_empty_with_no_extra_fields() -> TestResult {
+    let event: Event = serde_json::from_value(json!({ "name": "deploy" }))?;
+
+    assert_eq!(event.name, "deploy");
+    assert!(event.extra.is_empty());
+    Ok(())
+}
+
+#[test]
+fn rest_accepts_a_non_hashmap_map_type() -> TestResult {
+    let event: OrderedEvent = serde_json::from_value(json!({
+        "name": "deploy",
+        "region": "eu-west-1",
+    }))?;
+
+    assert_eq!(event.name, "deploy");
+    assert_eq!(event.extra["region"], json!("eu-west-1"));
+    Ok(())
+}
+
+#[test]
+fn plain_struct_tolerates_unknown_fields() -> TestResult {
+    let plain: Plain = serde_json::from_value(json!({ "id": 1, "unexpected": true }))?;
+
+    assert_eq!(plain, Plain { id: 1 });
+    Ok(())
+}
+
+#[test]
+fn capture_routes_extras_through_a_method() -> TestResult {
+    let counting: Counting = serde_json::from_value(json!({
+        "id": 7,
+        "a": 1,
+        "b": 2,
+    }))?;
+
+    assert_eq!(counting.id, 7);
+    assert_eq!(counting.tally.count, 2);
+    Ok(())
+}
@@ -62,11 +62,146 @@ impl WithSerdeAsAttrs {
     }
 }
 
+#[derive(SerializeMore)]
+struct PlainTuple(u32, u32);
+
+#[derive(SerializeMore)]
+#[more(k = "sum", v = "sum")]
+struct TupleWithExtra(u32, u32);
+
+impl TupleWithExtra {
+    fn sum(&self) -> u32 {
+        self.0 + self.1
+    }
+}
+
+#[derive(SerializeMore)]
+#[more(k = "sum", v = "sum", data_key = "payload")]
+struct TupleWithExtraAndDataKey(u32, u32);
+
+impl TupleWithExtraAndDataKey {
+    fn sum(&self) -> u32 {
+        self.0 + self.1
+    }
+}
+
+#[derive(SerializeMore)]
+struct PlainUnit;
+
+#[derive(SerializeMore)]
+#[more(k = "label")]
+struct UnitWithExtra;
+
+impl UnitWithExtra {
+    const fn label(&self) -> &'static str {
+        "unit"
+    }
+}
+
+#[derive(SerializeMore)]
+#[more(k = "next", v = "next", skip_serializing_if = "Option::is_none")]
+struct LinkedNode {
+    value: u32,
+}
+
+impl LinkedNode {
+    fn next(&self) -> Option<u32> {
+        (self.value < 10).then_some(self.value + 1)
+    }
+}
+
+struct Duration(u32);
+
+impl std::fmt::Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}ms", self.0)
+    }
+}
+
+#[derive(SerializeMore)]
+#[more(k = "elapsed", v = "elapsed", as = "display")]
+struct Timing {
+    label: &'static str,
+}
+
+impl Timing {
+    fn elapsed(&self) -> Duration {
+        Duration(42)
+    }
+}
+
+#[derive(Serialize)]
+struct Metadata {
+    source: &'static str,
+    version: u8,
+}
+
+#[derive(SerializeMore)]
+#[more(flatten = "metadata")]
+struct WithFlatten {
+    id: u32,
+}
+
+impl WithFlatten {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            source: "api",
+            version: 2,
+        }
+    }
+}
+
+#[derive(SerializeMore)]
+#[more(k = "kind", v = "kind")]
+enum Shape {
+    Circle { radius: u32 },
+    Square(u32),
+    Point,
+}
+
+impl Shape {
+    fn kind(&self) -> &'static str {
+        match self {
+            Shape::Circle { .. } => "circle",
+            Shape::Square(_) => "square",
+            Shape::Point => "point",
+        }
+    }
+}
+
+#[derive(SerializeMore)]
+#[serde(rename_all = "kebab-case")]
+#[more(k = "kind", v = "kind")]
+enum ShapeWithSerdeAttrs {
+    BigCircle { radius_value: u32 },
+}
+
+impl ShapeWithSerdeAttrs {
+    fn kind(&self) -> &'static str {
+        match self {
+            ShapeWithSerdeAttrs::BigCircle { .. } => "big-circle",
+        }
+    }
+}
+
 #[rstest]
 #[case::struct_single(&Basic { normal_field: 7 }, json!({"normal_field":7, "normal_field_squared":49}))]
 #[case::struct_multiple(&Multi { x: 3 }, json!({"x":3,"x_1":4,"x_2":5}))]
 #[case::serde_attrs(&WithSerdeAttrs { field_name: 1, opt_value: None }, json!({"field-name":1, "extraVal":"ok"}))]
 #[case::serde_with(&WithSerdeAsAttrs { payload: vec![0x0a, 0xff] }, json!({"payload":"0aff","payload_len":2}))]
+#[case::plain_tuple(&PlainTuple(1, 2), json!([1, 2]))]
+#[case::tuple_with_extra(&TupleWithExtra(1, 2), json!({"data":[1,2],"sum":3}))]
+#[case::tuple_with_extra_and_data_key(&TupleWithExtraAndDataKey(1, 2), json!({"payload":[1,2],"sum":3}))]
+#[case::skip_serializing_if_present(&LinkedNode { value: 3 }, json!({"value":3,"next":4}))]
+#[case::skip_serializing_if_suppressed(&LinkedNode { value: 10 }, json!({"value":10}))]
+#[case::as_display(&Timing { label: "query" }, json!({"label":"query","elapsed":"42ms"}))]
+#[case::flatten(&WithFlatten { id: 1 }, json!({"id":1,"source":"api","version":2}))]
+#[case::plain_unit(&PlainUnit, json!(null))]
+#[case::unit_with_extra(&UnitWithExtra, json!({"label":"unit"}))]
+#[case::enum_struct_variant(&Shape::Circle { radius: 2 }, json!({"radius":2,"kind":"circle"}))]
+#[case::enum_tuple_variant(&Shape::Square(4), json!({"data":4,"kind":"square"}))]
+#[case::enum_unit_variant(&Shape::Point, json!({"kind":"point"}))]
+#[case::enum_serde_attrs(&ShapeWithSerdeAttrs::BigCircle { radius_value: 5 }, json!({"radius-value":5,"kind":"big-circle"}))]
 fn serialize_more<T: Serialize>(
     #[case] input: T,
     #[case] expected: serde_json::Value,